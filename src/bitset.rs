@@ -0,0 +1,39 @@
+const BITS: usize = 64;
+
+/// A fixed-size bitset backed by a flat `Vec<u64>`. The candidate pool for a given run is known
+/// and bounded up front, so representing "is candidate `i` still valid" as a bitmap is a lot
+/// cheaper to build and intersect than a `HashSet<&str>` per witness: narrowing down to the
+/// words that survive every witness becomes a single bitwise AND fold instead of a scan of every
+/// set for every word.
+#[derive(Clone)]
+pub struct Bitset {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl Bitset {
+    pub fn new(len: usize) -> Bitset {
+        Bitset {
+            len: len,
+            words: vec![0; (len + BITS - 1) / BITS],
+        }
+    }
+
+    pub fn set(&mut self, index: usize) {
+        self.words[index / BITS] |= 1 << (index % BITS);
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        self.words[index / BITS] & (1 << (index % BITS)) != 0
+    }
+
+    pub fn intersect_with(&mut self, other: &Bitset) {
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a &= *b;
+        }
+    }
+
+    pub fn indices(&self) -> Vec<usize> {
+        (0..self.len).filter(|&i| self.get(i)).collect()
+    }
+}
@@ -0,0 +1,35 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use password::PasswordParseError;
+
+/// Everything that can go wrong turning candidate/witness files into a list of `Password`s,
+/// carrying enough context--which source, which line, what the line actually said--that a user
+/// fixing a large pasted list can see every problem at once instead of finding out about the
+/// first one and nothing else.
+#[derive(Debug)]
+pub enum AppError {
+    Io { source: Option<String>, cause: io::Error },
+    Parse { source: Option<String>, line: usize, text: String, cause: PasswordParseError },
+    /// A command-line argument was missing or didn't name anything we understand.
+    Usage(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AppError::Io { ref source, ref cause } => match *source {
+                Some(ref path) => write!(f, "{}: {}", path, cause),
+                None => write!(f, "{}", cause),
+            },
+            AppError::Parse { ref source, line, ref text, ref cause } => match *source {
+                Some(ref path) => write!(f, "{}:{}: \"{}\": {}", path, line, text, cause),
+                None => write!(f, "line {}: \"{}\": {}", line, text, cause),
+            },
+            AppError::Usage(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for AppError {}
@@ -1,16 +1,22 @@
 use std::collections::HashSet;
+use std::env;
+use std::fs::File;
 use std::io;
 use std::io::BufRead;
+use std::io::BufReader;
+use std::process;
 
+mod bitset;
+mod error;
+mod metric;
 mod password;
+mod solver;
 
+use bitset::Bitset;
+use error::AppError;
+use metric::Metric;
 use password::Password;
-
-#[derive(Debug)]
-enum Failure {
-    Input,
-    Validation,
-}
+use solver::Strategy;
 
 fn main() {
     // Each password represents a given string and its putative distance to the "correct" string,
@@ -22,45 +28,214 @@ fn main() {
     // terminal in existence. This tool assists in that task by accepting a list of passwords (of
     // the form <word> [<distance>]) on standard in and then printing all those words which are
     // valid candidates for all witnesses, thereby narrowing down the user's options considerably.
-    match read_passwords() {
-        Err(e) => panic!("{:?}", e),
+    //
+    // The source files are just lists of candidates and witnesses, one per line; a user will
+    // typically want to keep a big reusable word list in one file and jot the per-session
+    // witnesses down in another, so we accept any number of paths on the command line and
+    // concatenate their contents. With no paths given we fall back to standard in, same as ever.
+    //
+    // Passing `--solve` switches from a one-shot filter into an interactive assistant that
+    // recommends the next guess instead of just printing the surviving candidates. `--metric`
+    // picks which `Metric` decides whether a candidate is still consistent with a witness; it
+    // defaults to `positional`, Robco's classic same-length comparison. `--strategy` picks how
+    // `--solve` ranks its recommendations--`minimax` or `entropy`--and defaults to `entropy`.
+    let mut paths = Vec::new();
+    let mut interactive = false;
+    let mut metric = Metric::Positional;
+    let mut strategy = Strategy::Entropy;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--solve" {
+            interactive = true;
+        } else if arg == "--metric" {
+            let name = match args.next() {
+                Some(name) => name,
+                None => fail(&[AppError::Usage("--metric requires a value".to_string())]),
+            };
+            metric = match name.parse() {
+                Ok(metric) => metric,
+                Err(message) => fail(&[AppError::Usage(message)]),
+            };
+        } else if arg == "--strategy" {
+            let name = match args.next() {
+                Some(name) => name,
+                None => fail(&[AppError::Usage("--strategy requires a value".to_string())]),
+            };
+            strategy = match name.parse() {
+                Ok(strategy) => strategy,
+                Err(message) => fail(&[AppError::Usage(message)]),
+            };
+        } else {
+            paths.push(arg);
+        }
+    }
+
+    match read_passwords(&paths) {
+        // A single bad line used to kill the whole run with no indication of where; now we
+        // report every problem we found, in one pass, so a large pasted list can be fixed in one
+        // go instead of one panic at a time. We report and exit ourselves rather than returning
+        // the error from `main`, because the runtime's own `Result` handling would print a second,
+        // `Debug`-formatted copy of whatever we report here.
+        Err(diagnostics) => fail(&diagnostics),
         Ok(pairs) => {
-            // Here we have a list of lists containing all valid words for each word with a known
-            // distance. From these, we will print only those words appearing in all lists.
-            let valid_words: Vec<HashSet<&str>> = pairs.iter()
-                .filter_map(|pair| match pair.distance() {
-                    None => None,
-                    Some(distance) => Some(pairs.iter()
-                        .filter(|other| distance == other.closeness_to(&pair))
-                        .map(|pair| pair.word())
-                        .collect())
-                }).collect();
-
-            match valid_words.first() {
-                None => println!("At least one word must have a known distance"),
-                Some(first) => {
-                    let shared_words = first.iter().filter(|&word|
-                        valid_words.iter().skip(1).all(|set| set.contains(word))
-                    );
-
-                    for word in shared_words {
-                        println!("{}", word);
+            if interactive {
+                run_solver(pairs, metric, strategy);
+            } else {
+                run_filter(&pairs, metric);
+            }
+        }
+    }
+}
+
+fn fail(diagnostics: &[AppError]) -> ! {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    process::exit(1);
+}
+
+fn run_filter(pairs: &[Password], metric: Metric) {
+    // Every witness has an opinion about which candidates are still possible: candidate `i`
+    // survives witness `w` iff its closeness to `w` equals `w`'s reported distance. We assign
+    // each pair an index 0..pairs.len() and record that opinion as a bitmask rather than a
+    // `HashSet<&str>`, so narrowing down to the words that survive every witness is one bitwise
+    // AND fold instead of a scan of every set for every word.
+    let masks: Vec<Bitset> = pairs.iter()
+        .filter_map(|pair| match pair.distance() {
+            None => None,
+            Some(distance) => {
+                let mut mask = Bitset::new(pairs.len());
+                for (i, other) in pairs.iter().enumerate() {
+                    if distance == other.closeness_to(&pair, metric) {
+                        mask.set(i);
                     }
                 }
+                Some(mask)
+            }
+        }).collect();
+
+    match masks.split_first() {
+        None => println!("At least one word must have a known distance"),
+        Some((first, rest)) => {
+            let mut shared = first.clone();
+            for mask in rest {
+                shared.intersect_with(mask);
+            }
+
+            // The bitset indexes *pairs*, not distinct words, so the same word read from two
+            // files (e.g. once as a plain candidate, once as a witness) surfaces at more than
+            // one index. Dedupe by word text here, same as the `HashSet<&str>` this replaced did.
+            let mut seen = HashSet::new();
+            for i in shared.indices() {
+                let word = pairs[i].word();
+                if seen.insert(word) {
+                    println!("{}", word);
+                }
+            }
+        }
+    }
+}
+
+// Interactively recommend the next guess instead of just filtering once. `pairs` starts out as
+// whatever candidates and witnesses the user supplied, and grows by one witness every time the
+// terminal reports a likeness for a guess, until only one candidate remains.
+fn run_solver(mut pairs: Vec<Password>, metric: Metric, strategy: Strategy) {
+    loop {
+        // Dedupe by word text, same as `run_filter` does: the same word can show up more than
+        // once across files (e.g. listed in both a candidate file and a session file), and we
+        // don't want it scored and printed twice in "Best guesses," wasting a slot in the top-5.
+        let mut seen = HashSet::new();
+        let consistent: Vec<&Password> = pairs.iter()
+            .filter(|candidate| pairs.iter()
+                .filter_map(|witness| witness.distance().map(|distance| (witness, distance)))
+                .all(|(witness, distance)| distance == witness.closeness_to(candidate, metric))
+            )
+            .filter(|candidate| seen.insert(candidate.word()))
+            .collect();
+
+        match consistent.len() {
+            0 => {
+                println!("No candidates are consistent with every witness; check your input.");
+                return;
+            }
+            1 => {
+                println!("The password is: {}", consistent[0].word());
+                return;
+            }
+            _ => {}
+        }
+
+        let recommendations = solver::recommend(&consistent, strategy, metric);
+        println!("{} candidates remain. Best guesses:", consistent.len());
+        for recommendation in recommendations.iter().take(5) {
+            println!("  {} (score {:.3})", recommendation.word, recommendation.score);
+        }
+
+        println!("Enter the guess you used and the likeness it returned (e.g. `reactor 3`), \
+                   or a blank line to quit:");
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line).is_err() || line.trim().is_empty() {
+            return;
+        }
+
+        match line.trim().parse() {
+            Ok(witness @ Password::Witness(_, _)) => pairs.push(witness),
+            _ => println!("Expected `<word> <likeness>`; try again."),
+        }
+    }
+}
+
+fn read_passwords(paths: &[String]) -> Result<Vec<Password>, Vec<AppError>> {
+    if paths.is_empty() {
+        let handle = io::stdin();
+
+        // This is another one of those cases where a return statement mollifies the borrow
+        // checker but a simple expression does not. I'm surprised these are still cropping up;
+        // I had thought they were fixed. It is possible that this is a regression.
+        return read_lines(handle.lock(), None);
+    }
+
+    let mut passwords = Vec::new();
+    let mut diagnostics = Vec::new();
+    for path in paths {
+        match File::open(path) {
+            Err(cause) => diagnostics.push(AppError::Io { source: Some(path.clone()), cause: cause }),
+            Ok(file) => match read_lines(BufReader::new(file), Some(path.clone())) {
+                Ok(mut lines) => passwords.append(&mut lines),
+                Err(mut errors) => diagnostics.append(&mut errors),
             }
         }
     }
+
+    if diagnostics.is_empty() { Ok(passwords) } else { Err(diagnostics) }
 }
 
-fn read_passwords() -> Result<Vec<Password>, Failure> {
-    let handle = io::stdin();
-
-    // This is another one of those cases where a return statement mollifies the borrow checker
-    // but a simple expression does not. I'm surprised these are still cropping up; I had thought
-    // they were fixed. It is possible that this is a regression.
-    return handle.lock().lines()
-        .map(|line| line
-            .map_err(|_| Failure::Input)
-            .and_then(|line| line.parse().map_err(|_| Failure::Validation))
-        ).collect();
+fn read_lines<R: BufRead>(reader: R, source: Option<String>) -> Result<Vec<Password>, Vec<AppError>> {
+    let mut passwords = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line_number = i + 1;
+        match line {
+            Err(cause) => diagnostics.push(AppError::Io { source: source.clone(), cause: cause }),
+            Ok(raw) => {
+                let text = raw.trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+
+                match text.parse() {
+                    Ok(password) => passwords.push(password),
+                    Err(cause) => diagnostics.push(AppError::Parse {
+                        source: source.clone(),
+                        line: line_number,
+                        text: text,
+                        cause: cause,
+                    }),
+                }
+            }
+        }
+    }
+
+    if diagnostics.is_empty() { Ok(passwords) } else { Err(diagnostics) }
 }
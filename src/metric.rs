@@ -0,0 +1,125 @@
+use std::str::FromStr;
+
+/// How to compare two passwords' similarity. Robco's own hint is always a count of
+/// correctly-placed letters (`Positional`), but its "high security" variants permit different
+/// access levels to use different password lengths, and a positional comparison silently
+/// truncates to the shorter word in that case. The edit-distance metrics give a meaningful
+/// answer across mixed-length files instead.
+#[derive(Clone, Copy)]
+pub enum Metric {
+    Positional,
+    Levenshtein,
+    DamerauLevenshtein,
+}
+
+impl Metric {
+    pub fn distance(&self, a: &str, b: &str) -> usize {
+        match *self {
+            Metric::Positional => positional(a, b),
+            Metric::Levenshtein => levenshtein(a, b, false),
+            Metric::DamerauLevenshtein => levenshtein(a, b, true),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "positional" => Ok(Metric::Positional),
+            "levenshtein" => Ok(Metric::Levenshtein),
+            "damerau-levenshtein" | "damerau" => Ok(Metric::DamerauLevenshtein),
+            other => Err(format!("unknown metric: {}", other)),
+        }
+    }
+}
+
+fn positional(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).filter(|&(x, y)| x == y).count()
+}
+
+// The classic Levenshtein table, fill one row at a time: `d[i][j]` is the edit distance between
+// the first `i` characters of `wide` and the first `j` characters of `narrow`. Damerau's
+// transposition rule looks two characters back in both words, so we keep the previous two rows
+// (the older one is only read when transposition is enabled); this stays O(min(m, n)) space
+// rather than the full (m+1)x(n+1) grid.
+fn levenshtein(a: &str, b: &str, transposition: bool) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (wide, narrow) = if b.len() <= a.len() { (a, b) } else { (b, a) };
+    let (m, n) = (wide.len(), narrow.len());
+
+    let mut prev2: Vec<usize> = vec![0; n + 1];
+    let mut prev: Vec<usize> = (0..n + 1).collect();
+    let mut curr: Vec<usize> = vec![0; n + 1];
+
+    for i in 1..m + 1 {
+        curr[0] = i;
+        for j in 1..n + 1 {
+            let cost = if wide[i - 1] == narrow[j - 1] { 0 } else { 1 };
+            let mut value = (prev[j] + 1)
+                .min(curr[j - 1] + 1)
+                .min(prev[j - 1] + cost);
+
+            if transposition && i > 1 && j > 1
+                && wide[i - 1] == narrow[j - 2] && wide[i - 2] == narrow[j - 1]
+            {
+                value = value.min(prev2[j - 2] + 1);
+            }
+
+            curr[j] = value;
+        }
+
+        prev2 = prev;
+        prev = curr.clone();
+    }
+
+    prev[n]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positional_counts_matching_positions() {
+        assert_eq!(positional("apple", "apply"), 4);
+        assert_eq!(positional("abc", "xyz"), 0);
+        assert_eq!(positional("abc", "ab"), 2);
+    }
+
+    #[test]
+    fn levenshtein_matches_known_distances() {
+        assert_eq!(levenshtein("kitten", "sitting", false), 3);
+        assert_eq!(levenshtein("abc", "abc", false), 0);
+        assert_eq!(levenshtein("", "abc", false), 3);
+        assert_eq!(levenshtein("ab", "ba", false), 2);
+    }
+
+    #[test]
+    fn damerau_counts_an_adjacent_transposition_as_one_edit() {
+        assert_eq!(levenshtein("ab", "ba", true), 1);
+        assert_eq!(levenshtein("kitten", "sitting", true), 3);
+    }
+
+    #[test]
+    fn metric_dispatches_to_the_right_algorithm() {
+        assert_eq!(Metric::Positional.distance("apple", "apply"), 4);
+        assert_eq!(Metric::Levenshtein.distance("kitten", "sitting"), 3);
+        assert_eq!(Metric::DamerauLevenshtein.distance("ab", "ba"), 1);
+    }
+
+    #[test]
+    fn metric_parses_from_its_cli_names() {
+        assert!(match "positional".parse() {
+            Ok(Metric::Positional) => true,
+            _ => false,
+        });
+        assert!(match "damerau".parse() {
+            Ok(Metric::DamerauLevenshtein) => true,
+            _ => false,
+        });
+        assert!("bogus".parse::<Metric>().is_err());
+    }
+}
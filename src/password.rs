@@ -1,5 +1,8 @@
+use std::fmt;
 use std::str::FromStr;
 
+use metric::Metric;
+
 pub enum Password {
     Candidate(String),
     Witness(String, usize),
@@ -28,26 +31,34 @@ impl Password {
         }
     }
 
-    // I was originally using `strsim` and either Hamming or Levenshtein for this comparison, but
-    // the reality is that I don't *need* an external library, because this comparison is just too
-    // simple to justify it. Using `zip` for this does not handle the case where one password may
-    // be longer or shorter than the other, but that's irrelevant for all known production verions
-    // of Robco's operating system, because all passwords were nominally required to be the same
-    // length, system-wide. Some "high security" variations permit different user access levels
-    // to have different required password lengths, but any given user could be granted only one
-    // access level, so this is still irrelevant in practice because users of different access
-    // levels are actually stored in different password files. Anyway, if you want admin access,
-    // why bother trying to crack a luser-level password?
-    pub fn closeness_to(&self, other: &Password) -> usize {
-        self.word().chars().zip(other.word().chars())
-            .filter(|&(a, b)| a == b)
-            .count()
+    // This used to just `zip` the two words' characters together, which doesn't handle the case
+    // where one password is longer or shorter than the other--fine for the common case where
+    // Robco requires every password on a system to share one length, but not for the "high
+    // security" variants that give different access levels different required lengths. The
+    // `metric` now decides how this comparison actually works, so the caller can pick something
+    // that degrades gracefully across mixed-length files instead of silently truncating.
+    pub fn closeness_to(&self, other: &Password, metric: Metric) -> usize {
+        metric.distance(self.word(), other.word())
     }
 }
 
+/// Why a line failed to become a `Password`. Kept separate from any notion of *which* line or
+/// file it came from--that context belongs to whoever is reading lines, not to the parser.
+#[derive(Debug)]
 pub enum PasswordParseError {
-    NoInput,
-    BadDistance,
+    EmptyLine,
+    NonNumericDistance,
+    InvalidDistance,
+}
+
+impl fmt::Display for PasswordParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PasswordParseError::EmptyLine => write!(f, "line has no word on it"),
+            PasswordParseError::NonNumericDistance => write!(f, "distance is not a number"),
+            PasswordParseError::InvalidDistance => write!(f, "distance is negative or too large"),
+        }
+    }
 }
 
 impl FromStr for Password {
@@ -60,13 +71,34 @@ impl FromStr for Password {
         // strings or something. Keep it simple, amirite?
         let mut segments = s.split(' ');
         match segments.next() {
-            None => Err(PasswordParseError::NoInput),
+            None | Some("") => Err(PasswordParseError::EmptyLine),
             Some(word) => match segments.next() {
                 None => Ok(Password::candidate(word)),
-                Some(distance) => distance.parse()
-                    .map_err(|_| PasswordParseError::BadDistance)
+                Some(distance) => parse_distance(distance)
                     .map(|distance| Password::witness(word, distance))
             }
         }
     }
 }
+
+// Distinguishes a distance that isn't a number at all from one that is a number Robco could
+// never actually report--negative, or too large for `usize`--since the two are worth telling a
+// user apart: one's a typo, the other's a lie. We check "is this actually a number" by looking
+// at the characters rather than by parsing into a fixed-width integer first, because a decimal
+// string can be unambiguously numeric while still being too long for even `i128` to hold.
+fn parse_distance(s: &str) -> Result<usize, PasswordParseError> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(PasswordParseError::NonNumericDistance);
+    }
+
+    if negative {
+        return Err(PasswordParseError::InvalidDistance);
+    }
+
+    digits.parse().map_err(|_| PasswordParseError::InvalidDistance)
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use metric::Metric;
+use password::Password;
+
+/// How to rank a candidate guess by the partitions it would carve the remaining words into.
+#[derive(Clone, Copy)]
+pub enum Strategy {
+    /// Minimize the largest partition--the fewest words that could possibly survive the worst
+    /// likeness response, guaranteeing the fastest guaranteed narrowing.
+    Minimax,
+    /// Maximize the Shannon entropy of the partition sizes--the guess expected to narrow the
+    /// field the most on average, across all the likeness values it might produce.
+    Entropy,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minimax" => Ok(Strategy::Minimax),
+            "entropy" => Ok(Strategy::Entropy),
+            other => Err(format!("unknown strategy: {}", other)),
+        }
+    }
+}
+
+/// A candidate guess together with the score it earned under the chosen `Strategy`. Higher is
+/// always better, regardless of strategy.
+pub struct Recommendation<'a> {
+    pub word: &'a str,
+    pub score: f64,
+}
+
+/// Score every word in `consistent` by how well it would narrow `consistent` itself if guessed
+/// next, and return the recommendations best-score first.
+///
+/// `consistent` must already be filtered down to words that agree with every witness observed
+/// so far--a guess can only be recommended if it is itself still a possible answer, or we'd be
+/// spending one of the terminal's four attempts on a word we already know is wrong.
+pub fn recommend<'a>(
+    consistent: &[&'a Password],
+    strategy: Strategy,
+    metric: Metric,
+) -> Vec<Recommendation<'a>> {
+    let total = consistent.len() as f64;
+
+    let mut recommendations: Vec<Recommendation> = consistent.iter()
+        .map(|&guess| {
+            let mut partitions: HashMap<usize, usize> = HashMap::new();
+            for &word in consistent {
+                *partitions.entry(word.closeness_to(guess, metric)).or_insert(0) += 1;
+            }
+
+            let score = match strategy {
+                Strategy::Minimax => -(*partitions.values().max().unwrap() as f64),
+                Strategy::Entropy => partitions.values()
+                    .map(|&count| {
+                        let p = count as f64 / total;
+                        -p * p.log2()
+                    }).sum(),
+            };
+
+            Recommendation { word: guess.word(), score: score }
+        }).collect();
+
+    recommendations.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+    recommendations
+}